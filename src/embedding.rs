@@ -0,0 +1,78 @@
+/// Produces a dense vector representation of a chunk of text, for semantic
+/// (meaning-based) retrieval as opposed to the exact-term TF-IDF matcher.
+/// Implementations may wrap a local model or call out to an HTTP endpoint.
+pub trait EmbeddingProvider: Send + Sync + std::fmt::Debug {
+    fn embed(&self, text: &str) -> Vec<f32>;
+    fn dim(&self) -> usize;
+}
+
+/// A dependency-free local embedder: feature-hashes whitespace-separated
+/// terms into a fixed-size vector and L2-normalizes it. It's a stand-in for
+/// a real local model when none is configured, so semantic search degrades
+/// gracefully instead of requiring network access.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dim: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new(dim: usize) -> Self {
+        Self { dim }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl EmbeddingProvider for HashingEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; self.dim];
+
+        for term in text.split_whitespace() {
+            let bucket = term_hash(term) % self.dim as u64;
+            vector[bucket as usize] += 1.;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0. {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+
+        vector
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+fn term_hash(term: &str) -> u64 {
+    // FNV-1a, good enough to spread terms across buckets deterministically.
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in term.to_uppercase().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.;
+    }
+
+    let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+    if norm_a == 0. || norm_b == 0. {
+        0.
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
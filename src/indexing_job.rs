@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::model::Model;
+use crate::parser;
+
+/// A progress snapshot emitted by a running `IndexingJob`, polled by the
+/// REPL to report files discovered/indexed, the file currently being
+/// parsed, and elapsed time. No ETA is reported: `files_discovered` counts
+/// the files the walk has reached so far, not a total known in advance.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub files_discovered: usize,
+    pub files_indexed: usize,
+    pub current_path: Option<PathBuf>,
+    pub elapsed: Duration,
+}
+
+/// A cancellable background job that walks a directory and builds a `Model`
+/// from it, reporting `Progress` over a channel instead of the ad-hoc
+/// `println!`s `add_dir` uses. Mirrors the query-side threading in
+/// `process_query`/`dispatch_tasks`, but for the (much longer) initial
+/// indexing pass rather than a single query.
+pub struct IndexingJob {
+    progress_rx: Receiver<Progress>,
+    cancel: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Model>>,
+}
+
+impl IndexingJob {
+    /// Spawns the worker thread against `model` (already configured with
+    /// whatever include/exclude globs and embedder/scoring mode the caller
+    /// wants). If `save_to` is set, the worker persists whatever it indexed
+    /// to that path when it finishes or is cancelled, so a cancelled run
+    /// isn't lost.
+    pub fn start(dir_path: PathBuf, mut model: Model, save_to: Option<String>) -> Self {
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let cancel_ref = cancel.clone();
+
+        let handle = thread::spawn(move || {
+            let start = Instant::now();
+            model.register_dir(&dir_path);
+
+            let mut files_discovered = 0;
+            let mut files_indexed = 0;
+
+            let walker = match model.build_walker(&dir_path) {
+                Ok(walker) => walker,
+                Err(()) => {
+                    eprintln!("ERROR building the directory walker for {dir_path:?}");
+                    return model;
+                }
+            };
+
+            for entry in walker {
+                if cancel_ref.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        eprintln!("ERROR walking {dir_path:?} : {err}");
+                        continue;
+                    }
+                };
+
+                if entry.file_type().map_or(true, |ft| !ft.is_file()) {
+                    continue;
+                }
+
+                files_discovered += 1;
+                let file_path = entry.path().to_path_buf();
+
+                let _ = progress_tx.send(Progress {
+                    files_discovered,
+                    files_indexed,
+                    current_path: Some(file_path.clone()),
+                    elapsed: start.elapsed(),
+                });
+
+                let last_modified = match entry.metadata() {
+                    Ok(metadata) => match metadata.modified() {
+                        Ok(last_modified) => last_modified,
+                        Err(err) => {
+                            eprintln!("ERROR when querying last modified time : {err}");
+                            continue;
+                        }
+                    },
+                    Err(err) => {
+                        eprintln!("ERROR when querying metadata : {err}");
+                        continue;
+                    }
+                };
+
+                if let Ok(docs) = parser::parse_file(&file_path, model.text_field()) {
+                    for (doc_path, content) in docs {
+                        model.add_doc(doc_path, &content, last_modified);
+                    }
+                    files_indexed += 1;
+                }
+            }
+
+            if let Some(save_to) = &save_to {
+                model.save_to_file(save_to);
+            }
+
+            let _ = progress_tx.send(Progress {
+                files_discovered,
+                files_indexed,
+                current_path: None,
+                elapsed: start.elapsed(),
+            });
+
+            model
+        });
+
+        Self {
+            progress_rx,
+            cancel,
+            handle: Some(handle),
+        }
+    }
+
+    /// Signals the worker to stop walking after its current file. The
+    /// model it returns from `join` still holds whatever was indexed so far.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Drains every progress report queued since the last poll, without
+    /// blocking if the worker hasn't produced one yet.
+    pub fn poll(&self) -> Vec<Progress> {
+        self.progress_rx.try_iter().collect()
+    }
+
+    /// Blocks until the worker finishes (cancelled or not) and returns the
+    /// model it built.
+    pub fn join(mut self) -> Model {
+        match self.handle.take().unwrap().join() {
+            Ok(model) => model,
+            Err(_) => {
+                eprintln!("Indexing job thread panicked, returning an empty model");
+                Model::new()
+            }
+        }
+    }
+}
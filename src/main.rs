@@ -1,12 +1,31 @@
+mod embedding;
+mod indexing_job;
 mod lexer;
 mod model;
 mod parser;
+mod watcher;
 
 use std::env;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use indexing_job::IndexingJob;
+
+fn parse_glob_list(value: Option<String>) -> Vec<String> {
+    value
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
 fn prompt_request() -> Result<String, ()> {
     let mut request = String::new();
@@ -31,30 +50,127 @@ fn main() -> Result<(), ()> {
         }
     };
 
+    // Semantic/hybrid search and BM25 are opt-in via env vars so the default
+    // TF-IDF behavior doesn't change for existing users. The embedder is set
+    // unconditionally (it's cheap and dependency-free) so DOOGLE_QUERY_MODE
+    // works out of the box without also needing to rebuild the index. The
+    // embedder is passed into `load_from_file` or set on the seed model
+    // before `IndexingJob::start`, since `add_doc` only chunks/embeds a
+    // document if an embedder is already set at the time it's added.
+    let embedder: Arc<dyn embedding::EmbeddingProvider> =
+        Arc::new(embedding::HashingEmbedder::default());
+    // `None` leaves a loaded model's persisted scoring mode alone; a fresh
+    // model always needs one, so it falls back to the TfIdf default.
+    let scoring_mode_override = match env::var("DOOGLE_SCORING_MODE").as_deref() {
+        Ok("bm25") => Some(model::ScoringMode::Bm25),
+        Ok("tfidf") => Some(model::ScoringMode::TfIdf),
+        _ => None,
+    };
+    // Names the CSV column / JSON/NDJSON key that becomes a row's document
+    // body. Unset, a loaded model keeps its saved choice and a fresh one
+    // uses parser::parse_file's per-format default. On a loaded model it is
+    // applied after `load_from_file`, so the staleness re-parse done there
+    // still uses the saved choice.
+    let text_field = env::var("DOOGLE_TEXT_FIELD").ok();
+    // Comma-separated glob lists narrowing which files get indexed, on top
+    // of whatever .gitignore/.ignore/.doogleignore already hide. Only take
+    // effect on the initial walk (IndexingJob::start), since loading an
+    // existing save reconciles staleness per already-indexed source file
+    // rather than re-walking the directory.
+    let include_globs = parse_glob_list(env::var("DOOGLE_INCLUDE_GLOBS").ok());
+    let exclude_globs = parse_glob_list(env::var("DOOGLE_EXCLUDE_GLOBS").ok());
+
     let mut model;
 
     let loading_start = Instant::now();
     if Path::new(&save_file_name).exists() {
-        model = model::Model::load_from_file(&save_file_name);
+        model = model::Model::load_from_file(&save_file_name, Some(embedder.clone()));
+        if let Some(scoring_mode) = scoring_mode_override {
+            model.set_scoring_mode(scoring_mode);
+        }
+        if let Some(text_field) = text_field.clone() {
+            model.set_text_field(text_field);
+        }
         println!(
             "Took {elapsed:.2}s to load the model!",
             elapsed = loading_start.elapsed().as_secs_f32()
         );
     } else {
         println!("Creating the model...");
-        model = model::Model::new();
-        model.add_dir(&PathBuf::from("docs.gl"))?;
+        let mut seed_model = model::Model::new();
+        seed_model.set_embedder(embedder.clone());
+        seed_model.set_scoring_mode(scoring_mode_override.unwrap_or_default());
+        if let Some(text_field) = text_field {
+            seed_model.set_text_field(text_field);
+        }
+        for pattern in include_globs {
+            seed_model.add_include_glob(pattern);
+        }
+        for pattern in exclude_globs {
+            seed_model.add_exclude_glob(pattern);
+        }
+        let job = IndexingJob::start(PathBuf::from("docs.gl"), seed_model, None);
+
+        loop {
+            let mut done = false;
+            for progress in job.poll() {
+                done = progress.current_path.is_none();
+                print!(
+                    "\rIndexed {indexed}/{discovered} files ({elapsed:.1}s){current}   ",
+                    indexed = progress.files_indexed,
+                    discovered = progress.files_discovered,
+                    elapsed = progress.elapsed.as_secs_f32(),
+                    current = match progress.current_path {
+                        Some(path) => format!(" - {path:?}"),
+                        None => String::new(),
+                    }
+                );
+                std::io::stdout().flush().ok();
+            }
+
+            if done {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        }
+        println!();
+
+        model = job.join();
         println!(
             "Took {elapsed:.2}s to create the model!",
             elapsed = loading_start.elapsed().as_secs_f32()
         );
     }
 
-    let model = Arc::new(model);
+    let model = Arc::new(RwLock::new(model));
 
-    println!("Search among {length} files!", length = model.corpus_size());
+    // A failed watch (e.g. hitting the OS's inotify instance/watch limits on
+    // a large tree) shouldn't throw away a model that may have taken a long
+    // time to build; fall back to running without live updates instead.
+    let _watcher = match watcher::watch(model.clone()) {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            eprintln!("ERROR starting filesystem watcher : {err}, continuing without live updates");
+            None
+        }
+    };
+
+    println!(
+        "Search among {length} files!",
+        length = model.read().unwrap().corpus_size()
+    );
     println!("(type :quit when you're done)");
 
+    // DOOGLE_QUERY_MODE selects "semantic" (embedding cosine similarity) or
+    // "hybrid" (blended with TF-IDF/BM25 via DOOGLE_HYBRID_ALPHA, default
+    // 0.5); anything else keeps the plain TF-IDF/BM25 path.
+    let query_mode = env::var("DOOGLE_QUERY_MODE").unwrap_or_default();
+    let hybrid_alpha = env::var("DOOGLE_HYBRID_ALPHA")
+        .ok()
+        .and_then(|alpha| alpha.parse::<f32>().ok())
+        .unwrap_or(0.5);
+
     'request_loop: loop {
         let request = match prompt_request() {
             Ok(v) if v != ":quit" => v,
@@ -63,7 +179,11 @@ fn main() -> Result<(), ()> {
 
         let res_compute_start = Instant::now();
 
-        let results = model::process_query(model.clone(), &request, 4);
+        let results = match query_mode.as_str() {
+            "semantic" => model.read().unwrap().process_query_semantic(&request),
+            "hybrid" => model::process_query_hybrid(model.clone(), &request, 4, hybrid_alpha),
+            _ => model::process_query(model.clone(), &request, 4),
+        };
         let mut results = results.iter().collect::<Vec<_>>();
         results.sort_by(|(_, v1), (_, v2)| v2.partial_cmp(v1).unwrap());
 
@@ -85,7 +205,7 @@ fn main() -> Result<(), ()> {
         }
     }
 
-    model.save_to_file(&save_file_name);
+    model.read().unwrap().save_to_file(&save_file_name);
 
     Ok(())
 }
@@ -1,21 +1,58 @@
 use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use std::thread;
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
+use crate::embedding::{self, EmbeddingProvider};
 use crate::lexer;
 use crate::parser;
 
+/// Picks the on-disk encoding `save_to_file` writes. Bincode is the default
+/// since it's far smaller and faster to (de)serialize than JSON; naming the
+/// save file with a `.json` extension keeps the readable format available
+/// for debugging or exporting an index. `load_from_file` ignores this and
+/// sniffs the actual bytes instead, so a JSON save loads whatever its name.
+/// Missing fields are not filled in: only `text_field` has a default, so a
+/// save without `total_terms_count`, `scoring_mode`, `k1`, `b`, the glob
+/// lists or per-document `chunks` fails to load.
+enum PersistFormat {
+    Json,
+    Bincode,
+}
+
+impl PersistFormat {
+    fn from_file_name(file_name: &str) -> Self {
+        match Path::new(file_name).extension().and_then(OsStr::to_str) {
+            Some("json") => PersistFormat::Json,
+            _ => PersistFormat::Bincode,
+        }
+    }
+}
+
+/// A fixed-size window of a document's text, embedded for semantic search.
+/// `offset` is the index into the document's `Vec<char>` content where the
+/// chunk starts, kept around so a match can be traced back to a snippet.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Chunk {
+    offset: usize,
+    text: String,
+    embedding: Vec<f32>,
+}
+
+const CHUNK_SIZE: usize = 512;
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 struct Document {
     terms_count: usize,
     last_modified: SystemTime,
     tf: TermFreq,
+    chunks: Vec<Chunk>,
 }
 
 type TermFreq = HashMap<String, usize>;
@@ -24,12 +61,39 @@ type InvDocFreq = HashMap<String, usize>;
 
 type QueryResult = HashMap<PathBuf, f32>;
 
+/// A `Model` shared between the query threads and, once watching is enabled,
+/// the background re-indexing thread.
+pub type SharedModel = Arc<RwLock<Model>>;
+
+/// Selects how `process_query` scores a document against a query term.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ScoringMode {
+    TfIdf,
+    Bm25,
+}
+
+impl Default for ScoringMode {
+    fn default() -> Self {
+        ScoringMode::TfIdf
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Model {
     dirs: HashSet<PathBuf>,
     tfi: TermFreqIndex,
     idf: InvDocFreq,
     num_docs: usize,
+    total_terms_count: usize,
+    scoring_mode: ScoringMode,
+    k1: f32,
+    b: f32,
+    include_globs: Vec<String>,
+    exclude_globs: Vec<String>,
+    #[serde(default)]
+    text_field: Option<String>,
+    #[serde(skip)]
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
 }
 
 impl Model {
@@ -39,55 +103,184 @@ impl Model {
             tfi: TermFreqIndex::new(),
             idf: InvDocFreq::new(),
             num_docs: 0,
+            total_terms_count: 0,
+            scoring_mode: ScoringMode::TfIdf,
+            k1: 1.2,
+            b: 0.75,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            text_field: None,
+            embedder: None,
         }
     }
 
+    /// Selects the scoring formula used by `process_query`/`process_query_term`.
+    pub fn set_scoring_mode(&mut self, mode: ScoringMode) {
+        self.scoring_mode = mode;
+    }
+
+    /// Tunes BM25's length-normalization parameters. `k1` controls term
+    /// frequency saturation, `b` controls how much document length relative
+    /// to the corpus average penalizes a score. Ignored in `TfIdf` mode.
+    pub fn set_bm25_params(&mut self, k1: f32, b: f32) {
+        self.k1 = k1;
+        self.b = b;
+    }
+
+    /// Enables semantic search by configuring the embedding provider used to
+    /// chunk and embed documents on `add_doc`, and to embed queries in
+    /// `process_query_semantic`.
+    pub fn set_embedder(&mut self, embedder: Arc<dyn EmbeddingProvider>) {
+        self.embedder = Some(embedder);
+    }
+
+    /// Names the CSV column or JSON/NDJSON object key that becomes a row's
+    /// document body, overriding `parser::parse_file`'s per-format default.
+    /// See `parser::parse_file` for what happens when this isn't set.
+    pub fn set_text_field(&mut self, text_field: String) {
+        self.text_field = Some(text_field);
+    }
+
+    pub(crate) fn text_field(&self) -> Option<&str> {
+        self.text_field.as_deref()
+    }
+
     pub fn corpus_size(&self) -> usize {
         self.tfi.len()
     }
 
+    /// The directories passed to `add_dir`, for a watcher to subscribe to.
+    pub fn dirs(&self) -> &HashSet<PathBuf> {
+        &self.dirs
+    }
+
+    /// Records `dir_path` as one of the model's indexed directories without
+    /// walking it, for callers (like `indexing_job`) that do their own walk.
+    pub fn register_dir(&mut self, dir_path: &PathBuf) {
+        self.dirs.insert(dir_path.to_path_buf());
+    }
+
+    /// Whether every document already indexed from `source_path` has this
+    /// exact modification time, so a caller can skip a re-parse it's already
+    /// caught up on. `source_path` is a real filesystem path, not a document
+    /// key: `parser::parse_csv_file`/`parse_json_file`/`parse_ndjson_file`
+    /// key their rows as `source_path#<index>`, so a single source can back
+    /// several documents. Returns `false` when nothing is indexed for it yet.
+    pub fn is_source_up_to_date(&self, source_path: &Path, last_modified: SystemTime) -> bool {
+        let mut docs = self
+            .tfi
+            .iter()
+            .filter(|(doc_path, _)| parser::source_path(doc_path) == source_path)
+            .peekable();
+
+        docs.peek().is_some() && docs.all(|(_, doc)| doc.last_modified == last_modified)
+    }
+
+    /// Removes every document indexed from `source_path`, including every
+    /// `source_path#<index>` row a multi-document parser produced for it.
+    /// A no-op if nothing is indexed for it.
+    pub fn remove_docs_for_source(&mut self, source_path: &Path) {
+        let doc_paths = self
+            .tfi
+            .keys()
+            .filter(|doc_path| parser::source_path(doc_path) == source_path)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for doc_path in doc_paths {
+            self.remove_doc(&doc_path);
+        }
+    }
+
     pub fn save_to_file(&self, file_name: &str) {
         println!("Saving model to {file_name}...");
         let file = File::create(file_name).unwrap();
-        serde_json::to_writer(BufWriter::new(file), self).unwrap();
+        match PersistFormat::from_file_name(file_name) {
+            PersistFormat::Json => {
+                serde_json::to_writer(BufWriter::new(file), self).unwrap();
+            }
+            PersistFormat::Bincode => {
+                bincode::serialize_into(BufWriter::new(file), self).unwrap();
+            }
+        }
         println!("Done saving.")
     }
 
-    pub fn load_from_file(file_name: &str) -> Self {
+    /// Loads a previously saved model and, if `embedder` is set, configures
+    /// it before any staleness-triggered re-parsing below runs (so documents
+    /// re-added during that pass get chunked/embedded too, not just ones the
+    /// watcher touches afterward).
+    pub fn load_from_file(file_name: &str, embedder: Option<Arc<dyn EmbeddingProvider>>) -> Self {
         println!("Loading the model from {file_name}...");
-        let file = File::open(file_name).unwrap();
-        let mut model: Self = serde_json::from_reader(BufReader::new(file)).unwrap();
+        let bytes = fs::read(file_name).unwrap();
+
+        // Sniff the actual encoding instead of trusting the extension: a
+        // JSON save not named `*.json` is still JSON whatever
+        // `PersistFormat::from_file_name` would guess from `file_name`, and
+        // decoding it as bincode would fail.
+        let mut model: Self = match serde_json::from_slice(&bytes) {
+            Ok(model) => model,
+            Err(_) => match bincode::deserialize(&bytes) {
+                Ok(model) => model,
+                Err(err) => {
+                    eprintln!(
+                        "ERROR: {file_name} isn't a valid doogle save file (tried JSON and bincode) : {err}"
+                    );
+                    std::process::exit(1);
+                }
+            },
+        };
 
-        let mut invalid_paths: Vec<PathBuf> = Vec::new();
-        let mut updated_paths: Vec<(SystemTime, PathBuf)> = Vec::new();
+        if let Some(embedder) = embedder {
+            model.set_embedder(embedder);
+        }
 
-        for (file_path, doc) in &model.tfi {
-            match fs::metadata(&file_path) {
+        // Group by the real source file rather than by document key: a
+        // CSV/JSON/NDJSON file backs several `source#<index>` documents, none
+        // of which are real paths on disk, so staleness has to be checked
+        // once per source file and applied to every document it produced.
+        let sources = model
+            .tfi
+            .keys()
+            .map(|doc_path| parser::source_path(doc_path))
+            .collect::<HashSet<_>>();
+
+        let mut invalid_sources: Vec<PathBuf> = Vec::new();
+        let mut updated_sources: Vec<(SystemTime, PathBuf)> = Vec::new();
+
+        for source in &sources {
+            match fs::metadata(source) {
                 Ok(metadata) => {
                     let last_modified = metadata.modified().unwrap();
-                    if last_modified != doc.last_modified {
-                        updated_paths.push((last_modified, file_path.to_path_buf()));
+                    if !model.is_source_up_to_date(source, last_modified) {
+                        updated_sources.push((last_modified, source.to_path_buf()));
                     }
                 }
-                Err(_) => invalid_paths.push(file_path.to_path_buf()),
+                Err(_) => invalid_sources.push(source.to_path_buf()),
             }
         }
 
-        for doc_path in invalid_paths {
-            println!("Invalidating {doc_path:?}...");
-            model.remove_doc(&doc_path);
+        for source in invalid_sources {
+            println!("Invalidating {source:?}...");
+            model.remove_docs_for_source(&source);
         }
 
-        'update_iter: for (last_modified, doc_path) in updated_paths {
-            println!("Updating {doc_path:?}...");
-            let content = match parser::parse_xml_file(&doc_path) {
-                Ok(tokens) => tokens,
+        'update_iter: for (last_modified, source) in updated_sources {
+            println!("Updating {source:?}...");
+            // The file may have gained or lost rows since the last save, so
+            // drop every document it previously produced before re-parsing.
+            model.remove_docs_for_source(&source);
+
+            let docs = match parser::parse_file(&source, model.text_field()) {
+                Ok(docs) => docs,
                 Err(_) => {
-                    eprintln!("ERROR while parsing xml document");
+                    eprintln!("ERROR while parsing document");
                     continue 'update_iter;
                 }
             };
-            model.add_doc(doc_path, &content, last_modified);
+            for (doc_path, content) in docs {
+                model.add_doc(doc_path, &content, last_modified);
+            }
         }
 
         println!("Done loading model.");
@@ -95,42 +288,74 @@ impl Model {
         model
     }
 
+    /// Adds a glob pattern files must match to be indexed (in addition to
+    /// their extension being recognized by `parser::parse_file`).
+    pub fn add_include_glob(&mut self, pattern: String) {
+        self.include_globs.push(pattern);
+    }
+
+    /// Adds a glob pattern that excludes matching files from indexing, on
+    /// top of whatever `.gitignore`/`.ignore`/`.doogleignore` already hide.
+    pub fn add_exclude_glob(&mut self, pattern: String) {
+        self.exclude_globs.push(pattern);
+    }
+
+    /// Builds the directory walker `add_dir` and `IndexingJob` both use,
+    /// honoring `.gitignore`/`.ignore`/`.doogleignore` plus the
+    /// include/exclude globs registered on this model, so the two walking
+    /// paths can't drift apart.
+    pub(crate) fn build_walker(&self, dir_path: &Path) -> Result<ignore::Walk, ()> {
+        let mut overrides = ignore::overrides::OverrideBuilder::new(dir_path);
+        for pattern in &self.exclude_globs {
+            overrides
+                .add(&format!("!{pattern}"))
+                .map_err(|err| eprintln!("ERROR in exclude glob {pattern:?} : {err}"))?;
+        }
+        for pattern in &self.include_globs {
+            overrides
+                .add(pattern)
+                .map_err(|err| eprintln!("ERROR in include glob {pattern:?} : {err}"))?;
+        }
+        let overrides = overrides.build().map_err(|err| eprintln!("{err}"))?;
+
+        Ok(ignore::WalkBuilder::new(dir_path)
+            .add_custom_ignore_filename(".doogleignore")
+            .overrides(overrides)
+            .build())
+    }
+
     pub fn add_dir(&mut self, dir_path: &PathBuf) -> Result<(), ()> {
         println!("Indexing directory : {dir_path:?}");
-        let dir = fs::read_dir(dir_path).map_err(|err| eprintln!("{err}"))?;
 
         self.dirs.insert(dir_path.to_path_buf());
 
-        'files_iter: for file in dir {
-            let file = file.map_err(|err| eprintln!("ERROR file is incorrect : {err}"))?;
-            let file_path = file.path();
-            let file_type = file
-                .file_type()
-                .map_err(|err| eprintln!("ERROR when querying file type : {err}"))?;
+        let walker = self.build_walker(dir_path)?;
+
+        for entry in walker {
+            let entry = entry.map_err(|err| eprintln!("ERROR walking {dir_path:?} : {err}"))?;
 
-            if file_type.is_dir() {
-                self.add_dir(&file_path)?;
-                continue 'files_iter;
+            if entry.file_type().map_or(true, |ft| !ft.is_file()) {
+                continue;
             }
 
-            let file_ext = file_path.extension().and_then(std::ffi::OsStr::to_str);
-            let last_modified = file
-                .metadata()
-                .map_err(|err| eprintln!("ERROR when querying metadata : {err}"))?
-                .modified()
-                .map_err(|err| eprintln!("ERROR when querying last modified time : {err}"))?;
-
-            if let Some(ext) = file_ext {
-                let content = match ext {
-                    "xhtml" => parser::parse_xml_file(&file_path)?,
-                    _ => {
-                        println!("Skipping file {file_path:?}");
-                        continue 'files_iter;
-                    } // Skipping all files that we cannot parse yet
-                };
-                self.add_doc(file_path, &content, last_modified);
-            } else {
-                println!("Unknown file : {file_path:?}");
+            let file_path = entry.path().to_path_buf();
+            let last_modified = match entry.metadata() {
+                Ok(metadata) => metadata
+                    .modified()
+                    .map_err(|err| eprintln!("ERROR when querying last modified time : {err}"))?,
+                Err(err) => {
+                    eprintln!("ERROR when querying metadata : {err}");
+                    continue;
+                }
+            };
+
+            match parser::parse_file(&file_path, self.text_field()) {
+                Ok(docs) => {
+                    for (doc_path, content) in docs {
+                        self.add_doc(doc_path, &content, last_modified);
+                    }
+                }
+                Err(()) => println!("Skipping file {file_path:?}"),
             }
         }
 
@@ -168,20 +393,48 @@ impl Model {
             }
         }
 
+        let chunks = self.build_chunks(content);
+
         self.tfi.insert(
             doc_path,
             Document {
                 terms_count,
                 last_modified,
                 tf,
+                chunks,
             },
         );
         self.num_docs += 1;
+        self.total_terms_count += terms_count;
+    }
+
+    /// Splits `content` into fixed-size windows and embeds each one with the
+    /// configured embedding provider. Returns no chunks if semantic search
+    /// isn't enabled, so plain TF-IDF usage pays no embedding cost.
+    fn build_chunks(&self, content: &[char]) -> Vec<Chunk> {
+        let Some(embedder) = &self.embedder else {
+            return Vec::new();
+        };
+
+        content
+            .chunks(CHUNK_SIZE)
+            .enumerate()
+            .map(|(i, window)| {
+                let text = window.iter().collect::<String>();
+                let embedding = embedder.embed(&text);
+                Chunk {
+                    offset: i * CHUNK_SIZE,
+                    text,
+                    embedding,
+                }
+            })
+            .collect()
     }
 
     pub fn remove_doc(&mut self, doc_path: &PathBuf) {
         if let Some(doc) = self.tfi.remove(doc_path) {
             self.num_docs -= 1;
+            self.total_terms_count -= doc.terms_count;
 
             for term in doc.tf.keys() {
                 if let Some(count) = self.idf.get_mut(term) {
@@ -223,7 +476,14 @@ impl Model {
     fn process_query_term(&self, term: &str) -> QueryResult {
         let term = Model::clean_term(term);
 
-        let idf_value = self.get_idf(&term);
+        match self.scoring_mode {
+            ScoringMode::TfIdf => self.process_query_term_tfidf(&term),
+            ScoringMode::Bm25 => self.process_query_term_bm25(&term),
+        }
+    }
+
+    fn process_query_term_tfidf(&self, term: &str) -> QueryResult {
+        let idf_value = self.get_idf(term);
         if idf_value == 0. {
             return QueryResult::new();
         }
@@ -231,7 +491,7 @@ impl Model {
         let mut results = QueryResult::new();
 
         for path in self.tfi.keys() {
-            let tf_value = self.get_tf_doc(path, &term);
+            let tf_value = self.get_tf_doc(path, term);
             if tf_value == 0. {
                 // Skip to the next document if term isn't in the current one
                 continue;
@@ -251,6 +511,83 @@ impl Model {
 
         results
     }
+
+    /// `idf = ln((N - n_q + 0.5) / (n_q + 0.5) + 1)`, Robertson-Spärck Jones
+    /// with the usual +1 smoothing so the log's argument stays above 1 and
+    /// the idf never goes negative.
+    fn get_bm25_idf(&self, term: &str) -> f32 {
+        let n = self.num_docs as f32;
+        let n_q = self.idf.get(term).copied().unwrap_or(0) as f32;
+        ((n - n_q + 0.5) / (n_q + 0.5) + 1.).ln()
+    }
+
+    fn avgdl(&self) -> f32 {
+        if self.num_docs == 0 {
+            0.
+        } else {
+            self.total_terms_count as f32 / self.num_docs as f32
+        }
+    }
+
+    fn process_query_term_bm25(&self, term: &str) -> QueryResult {
+        if self.num_docs == 0 {
+            return QueryResult::new();
+        }
+
+        let idf_value = self.get_bm25_idf(term);
+        let avgdl = self.avgdl();
+
+        let mut results = QueryResult::new();
+
+        for (path, doc) in &self.tfi {
+            let Some(&f) = doc.tf.get(term) else {
+                // Skip to the next document if term isn't in the current one
+                continue;
+            };
+
+            let f = f as f32;
+            let dl = doc.terms_count as f32;
+            let length_norm = 1. - self.b + self.b * dl / avgdl;
+            let score = idf_value * (f * (self.k1 + 1.)) / (f + self.k1 * length_norm);
+
+            match results.get_mut(path) {
+                Some(v) => {
+                    *v += score;
+                }
+                None => {
+                    results.insert(path.to_path_buf(), score);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Embeds `request` and ranks documents by the best (max) cosine
+    /// similarity between the query and any one of their chunks. Returns an
+    /// empty result set when no embedding provider is configured.
+    pub fn process_query_semantic(&self, request: &str) -> QueryResult {
+        let Some(embedder) = &self.embedder else {
+            return QueryResult::new();
+        };
+
+        let query_embedding = embedder.embed(request);
+
+        let mut results = QueryResult::new();
+        for (path, doc) in &self.tfi {
+            let best_similarity = doc
+                .chunks
+                .iter()
+                .map(|chunk| embedding::cosine_similarity(&query_embedding, &chunk.embedding))
+                .fold(0f32, f32::max);
+
+            if best_similarity > 0. {
+                results.insert(path.to_path_buf(), best_similarity);
+            }
+        }
+
+        results
+    }
 }
 
 fn combine_results(results: &mut QueryResult, result: QueryResult) {
@@ -298,11 +635,7 @@ fn dispatch_tasks(threads_count: usize, tasks: usize) -> Vec<(usize, usize)> {
     dispatched
 }
 
-pub fn process_query(
-    model: Arc<Model>,
-    request: &str,
-    threads_count: usize,
-) -> HashMap<PathBuf, f32> {
+pub fn process_query(model: SharedModel, request: &str, threads_count: usize) -> QueryResult {
     let request = request.split_whitespace().collect::<Vec<_>>();
 
     let mut results = QueryResult::new();
@@ -320,7 +653,8 @@ pub fn process_query(
         result_threads.push(thread::spawn(move || {
             let mut results = QueryResult::new();
             for term in terms {
-                combine_results(&mut results, model_ref.process_query_term(&term));
+                let result = model_ref.read().unwrap().process_query_term(&term);
+                combine_results(&mut results, result);
             }
             results
         }));
@@ -339,3 +673,98 @@ pub fn process_query(
 
     results
 }
+
+/// Blends the exact-term TF-IDF ranking with the semantic (embedding) one,
+/// so paraphrased queries still surface relevant documents. `alpha` weighs
+/// the normalized TF-IDF score against the cosine score, e.g. `0.5` for an
+/// even split; the TF-IDF side is normalized by its own max score first
+/// since TF-IDF and cosine similarity live on different scales.
+pub fn process_query_hybrid(
+    model: SharedModel,
+    request: &str,
+    threads_count: usize,
+    alpha: f32,
+) -> QueryResult {
+    let tfidf_results = process_query(model.clone(), request, threads_count);
+    let semantic_results = model.read().unwrap().process_query_semantic(request);
+
+    let tfidf_max = tfidf_results
+        .values()
+        .cloned()
+        .fold(0f32, f32::max);
+
+    let mut results = QueryResult::new();
+
+    for (path, &tfidf_score) in &tfidf_results {
+        let normalized = if tfidf_max > 0. {
+            tfidf_score / tfidf_max
+        } else {
+            0.
+        };
+        results.insert(path.to_path_buf(), alpha * normalized);
+    }
+
+    for (path, semantic_score) in semantic_results {
+        *results.entry(path).or_insert(0.) += (1. - alpha) * semantic_score;
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(content: &str) -> Vec<char> {
+        content.chars().collect()
+    }
+
+    // Two-document corpus used by every BM25 test below: "a.txt" is "cat dog
+    // dog" (terms_count 3), "b.txt" is "cat cat cat bird" (terms_count 4),
+    // so num_docs = 2, avgdl = 3.5. Expected scores are computed by hand from
+    // the idf documented on `get_bm25_idf` and the term contribution
+    // idf * f*(k1+1) / (f + k1*(1 - b + b*dl/avgdl)), independently of the
+    // implementation, with k1 = 1.2, b = 0.75.
+    fn bm25_corpus() -> Model {
+        let mut model = Model::new();
+        model.set_scoring_mode(ScoringMode::Bm25);
+        let now = SystemTime::now();
+        model.add_doc(PathBuf::from("a.txt"), &chars("cat dog dog"), now);
+        model.add_doc(PathBuf::from("b.txt"), &chars("cat cat cat bird"), now);
+        model
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn bm25_scores_match_the_documented_formula() {
+        let model = bm25_corpus();
+
+        let dog_scores = model.process_query_term_bm25("DOG");
+        assert_close(dog_scores[&PathBuf::from("a.txt")], 0.9929736);
+
+        let cat_scores = model.process_query_term_bm25("CAT");
+        assert_close(cat_scores[&PathBuf::from("a.txt")], 0.19363807);
+        assert_close(cat_scores[&PathBuf::from("b.txt")], 0.27799524);
+    }
+
+    #[test]
+    fn bm25_omits_documents_missing_the_term() {
+        let model = bm25_corpus();
+
+        let bird_scores = model.process_query_term_bm25("BIRD");
+        assert_eq!(bird_scores.len(), 1);
+        assert!(bird_scores.contains_key(&PathBuf::from("b.txt")));
+    }
+
+    #[test]
+    fn bm25_returns_nothing_on_an_empty_corpus() {
+        let model = Model::new();
+        assert!(model.process_query_term_bm25("ANYTHING").is_empty());
+    }
+}
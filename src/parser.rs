@@ -1,17 +1,87 @@
-use std::io::BufReader;
+use std::ffi::OsStr;
+use std::fs;
 use std::fs::File;
-use std::path::PathBuf;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 
 use xml::reader::XmlEvent;
 use xml::EventReader;
 
+/// A single document extracted from a source file, alongside the path it
+/// should be indexed under. Most formats produce exactly one of these per
+/// file; row/line/element-oriented formats (CSV, NDJSON, JSON arrays)
+/// produce one per record.
+pub type ParsedDoc = (PathBuf, Vec<char>);
+
+/// Recovers the real filesystem path backing a document key. Single-document
+/// formats index documents under their own path, so this is the identity for
+/// them; multi-document formats (`parse_csv_file`, `parse_json_file`,
+/// `parse_ndjson_file`) key each row/line as `path#<index>`, and this strips
+/// that suffix back off so staleness checks and re-indexing can be done per
+/// source file instead of per (non-existent-on-disk) synthetic row path.
+pub fn source_path(doc_path: &Path) -> PathBuf {
+    let doc_path_str = doc_path.to_string_lossy();
+
+    match doc_path_str.rsplit_once('#') {
+        Some((base, suffix)) if !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()) => {
+            PathBuf::from(base)
+        }
+        _ => doc_path.to_path_buf(),
+    }
+}
+
+enum Format {
+    Xhtml,
+    PlainText,
+    Csv,
+    Json,
+    Ndjson,
+}
+
+impl Format {
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "xhtml" => Some(Format::Xhtml),
+            "txt" | "md" => Some(Format::PlainText),
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "ndjson" => Some(Format::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+/// Dispatches on `path`'s extension and returns the documents it contains.
+/// Returns `Err(())` for an extension we don't know how to parse yet.
+/// `text_field` names the column (CSV) or object key (JSON/NDJSON) that
+/// becomes a row's document body; `None` keeps each format's own default
+/// (every CSV column concatenated, or JSON/NDJSON's conventional `"text"`
+/// key). Ignored by the single-document formats.
+pub fn parse_file(path: &PathBuf, text_field: Option<&str>) -> Result<Vec<ParsedDoc>, ()> {
+    let ext = path.extension().and_then(OsStr::to_str).unwrap_or("");
+
+    match Format::from_extension(ext) {
+        Some(Format::Xhtml) => parse_xml_file(path).map(|content| vec![(path.clone(), content)]),
+        Some(Format::PlainText) => {
+            parse_text_file(path).map(|content| vec![(path.clone(), content)])
+        }
+        Some(Format::Csv) => parse_csv_file(path, text_field),
+        Some(Format::Json) => parse_json_file(path, text_field.unwrap_or("text")),
+        Some(Format::Ndjson) => parse_ndjson_file(path, text_field.unwrap_or("text")),
+        None => Err(()),
+    }
+}
+
 pub fn parse_xml_file(path: &PathBuf) -> Result<Vec<char>, ()> {
     let file = match File::open(path) {
         Ok(file) => BufReader::new(file),
-        Err(e) => { eprintln!("{e}"); return Err(()); }
+        Err(e) => {
+            eprintln!("{e}");
+            return Err(());
+        }
     };
 
-    let mut content = String::new(); 
+    let mut content = String::new();
 
     let parser = EventReader::new(BufReader::new(file));
     for e in parser {
@@ -30,3 +100,138 @@ pub fn parse_xml_file(path: &PathBuf) -> Result<Vec<char>, ()> {
 
     Ok(content.chars().collect::<Vec<_>>())
 }
+
+/// Plain text and Markdown are indexed verbatim, with no structural parsing.
+pub fn parse_text_file(path: &PathBuf) -> Result<Vec<char>, ()> {
+    let content = fs::read_to_string(path).map_err(|err| eprintln!("{err}"))?;
+    Ok(content.chars().collect::<Vec<_>>())
+}
+
+/// Turns a CSV file into one document per row, keyed by `path#<row index>`.
+/// When `text_column` names a header, only that column becomes the document
+/// body; when it is `None` or matches no header, every field in the row is
+/// concatenated.
+pub fn parse_csv_file(path: &PathBuf, text_column: Option<&str>) -> Result<Vec<ParsedDoc>, ()> {
+    let mut reader = csv::Reader::from_path(path).map_err(|err| eprintln!("{err}"))?;
+
+    let headers = reader.headers().map_err(|err| eprintln!("{err}"))?.clone();
+    let text_col_idx = text_column.and_then(|name| headers.iter().position(|h| h == name));
+
+    let mut docs = Vec::new();
+    for (row_idx, record) in reader.records().enumerate() {
+        let record = record.map_err(|err| eprintln!("ERROR parsing CSV row : {err}"))?;
+
+        let body = match text_col_idx {
+            Some(idx) => record.get(idx).unwrap_or("").to_string(),
+            None => record.iter().collect::<Vec<_>>().join(" "),
+        };
+
+        let doc_path = PathBuf::from(format!("{path}#{row_idx}", path = path.display()));
+        docs.push((doc_path, body.chars().collect::<Vec<_>>()));
+    }
+
+    Ok(docs)
+}
+
+/// Turns a JSON array (or a single top-level object) into one document per
+/// element, using `text_field` as the key holding the document body.
+pub fn parse_json_file(path: &PathBuf, text_field: &str) -> Result<Vec<ParsedDoc>, ()> {
+    let file = File::open(path).map_err(|err| eprintln!("{err}"))?;
+    let value: serde_json::Value =
+        serde_json::from_reader(BufReader::new(file)).map_err(|err| eprintln!("{err}"))?;
+
+    let objects = match value {
+        serde_json::Value::Array(values) => values,
+        other => vec![other],
+    };
+
+    let mut docs = Vec::new();
+    for (idx, object) in objects.into_iter().enumerate() {
+        let body = extract_text_field(&object, text_field);
+        let doc_path = PathBuf::from(format!("{path}#{idx}", path = path.display()));
+        docs.push((doc_path, body.chars().collect::<Vec<_>>()));
+    }
+
+    Ok(docs)
+}
+
+/// Same as [`parse_json_file`] but for newline-delimited JSON, where each
+/// line is its own JSON object instead of one top-level array.
+pub fn parse_ndjson_file(path: &PathBuf, text_field: &str) -> Result<Vec<ParsedDoc>, ()> {
+    let content = fs::read_to_string(path).map_err(|err| eprintln!("{err}"))?;
+
+    let mut docs = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let object: serde_json::Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("ERROR parsing NDJSON line {idx} : {e}");
+                continue;
+            }
+        };
+
+        let body = extract_text_field(&object, text_field);
+        let doc_path = PathBuf::from(format!("{path}#{idx}", path = path.display()));
+        docs.push((doc_path, body.chars().collect::<Vec<_>>()));
+    }
+
+    Ok(docs)
+}
+
+fn extract_text_field(value: &serde_json::Value, text_field: &str) -> String {
+    match value.get(text_field) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_path_strips_a_synthetic_row_suffix() {
+        assert_eq!(
+            source_path(Path::new("data.csv#3")),
+            PathBuf::from("data.csv")
+        );
+        assert_eq!(
+            source_path(Path::new("data.ndjson#0")),
+            PathBuf::from("data.ndjson")
+        );
+    }
+
+    #[test]
+    fn source_path_is_the_identity_for_a_plain_path() {
+        assert_eq!(
+            source_path(Path::new("docs/readme.txt")),
+            PathBuf::from("docs/readme.txt")
+        );
+    }
+
+    #[test]
+    fn source_path_only_strips_an_all_digit_suffix() {
+        // `#v2` isn't a row index, so it's part of the real name.
+        assert_eq!(
+            source_path(Path::new("release#v2.txt")),
+            PathBuf::from("release#v2.txt")
+        );
+    }
+
+    #[test]
+    fn source_path_misidentifies_a_real_name_ending_in_digits() {
+        // Known limitation: a real file named exactly like a synthetic row
+        // key (no extension, `#<digits>` suffix) is indistinguishable from
+        // one and gets its suffix stripped too. Asserted here so a future
+        // change to the stripping rule is a deliberate, visible decision.
+        assert_eq!(
+            source_path(Path::new("invoice#42")),
+            PathBuf::from("invoice")
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher as NotifyWatcher};
+
+use crate::model::SharedModel;
+use crate::parser;
+
+/// How long a path must go without a new event before we consider its write
+/// settled and worth re-parsing. Keeps an editor's several-writes-per-second
+/// autosave from triggering a re-index per write.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches every directory registered on `model` for filesystem changes and
+/// keeps the index current: a settled create/modify triggers a re-parse via
+/// `add_doc`, a delete triggers `remove_doc`. Returns the underlying watcher,
+/// which must be kept alive for as long as watching should continue.
+pub fn watch(model: SharedModel) -> notify::Result<notify::RecommendedWatcher> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    for dir in model.read().unwrap().dirs() {
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+    }
+
+    thread::spawn(move || {
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(Ok(event)) => {
+                    for path in event.paths {
+                        pending.insert(path, Instant::now());
+                    }
+                }
+                Ok(Err(err)) => eprintln!("ERROR from filesystem watcher : {err}"),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let settled = pending
+                .iter()
+                .filter(|(_, &seen_at)| seen_at.elapsed() >= DEBOUNCE)
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>();
+
+            for path in settled {
+                pending.remove(&path);
+                reindex(&model, &path);
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+fn reindex(model: &SharedModel, path: &PathBuf) {
+    let last_modified = match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        Ok(last_modified) => last_modified,
+        Err(_) => {
+            // The path no longer exists: drop every document it produced
+            // (a single row-oriented file can back many synthetic doc keys).
+            model.write().unwrap().remove_docs_for_source(path);
+            return;
+        }
+    };
+
+    if model.read().unwrap().is_source_up_to_date(path, last_modified) {
+        return;
+    }
+
+    let text_field = model.read().unwrap().text_field().map(String::from);
+    match parser::parse_file(path, text_field.as_deref()) {
+        Ok(docs) => {
+            let mut model = model.write().unwrap();
+            // The file may now produce a different number of rows than it
+            // did before, so drop its old documents before re-adding.
+            model.remove_docs_for_source(path);
+            for (doc_path, content) in docs {
+                println!("Re-indexing {doc_path:?}...");
+                model.add_doc(doc_path, &content, last_modified);
+            }
+        }
+        Err(()) => eprintln!("ERROR parsing {path:?}"),
+    }
+}